@@ -5,17 +5,122 @@ use crate::avm2::bytearray::Endian;
 use crate::avm2::class::{Class, ClassAttributes};
 use crate::avm2::method::Method;
 use crate::avm2::names::{Namespace, QName};
+use crate::avm2::object::loaderinfo_object::LoaderStream;
 use crate::avm2::object::{
-    ByteArrayObject, DomainObject, LoaderInfoObject, LoaderStream, Object, ScriptObject, TObject,
+    ByteArrayObject, DomainObject, EventObject, LoaderInfoObject, Object, ScriptObject, TObject,
 };
 use crate::avm2::scope::Scope;
 use crate::avm2::traits::Trait;
 use crate::avm2::value::Value;
-use crate::avm2::{AvmString, Error};
+use crate::avm2::{Avm2, AvmString, Error};
+use crate::context::UpdateContext;
 use crate::display_object::TDisplayObject;
 use gc_arena::{GcCell, MutationContext};
 use swf::{write_swf, Compression, Swf};
 
+/// Dispatches a plain (non-`ProgressEvent`, non-`HTTPStatusEvent`) event of
+/// the given name to a `LoaderInfo`.
+///
+/// This is called by `crate::loader::load_swf_into` as a load progresses,
+/// since that runs outside of any AVM2 activation.
+fn dispatch_simple_event<'gc>(
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    event_name: &'static str,
+) -> Result<(), Error> {
+    let event = EventObject::bare_default_event(context, event_name);
+
+    Avm2::dispatch_event(context, event, this)
+}
+
+/// Fires the `open` event once the load operation has actually begun.
+pub fn fire_open_event<'gc>(
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+) -> Result<(), Error> {
+    dispatch_simple_event(context, this, "open")
+}
+
+/// Fires a `ProgressEvent` carrying the `bytesLoaded`/`bytesTotal` observed
+/// so far.
+pub fn fire_progress_event<'gc>(
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    bytes_loaded: u32,
+    bytes_total: u32,
+) -> Result<(), Error> {
+    let progress_evt_cls = context.avm2.classes().progressevent;
+    let mut activation = Activation::from_nothing(context.reborrow());
+    let event = progress_evt_cls.construct(
+        &mut activation,
+        &[
+            "progress".into(),
+            false.into(),
+            false.into(),
+            bytes_loaded.into(),
+            bytes_total.into(),
+        ],
+    )?;
+
+    Avm2::dispatch_event(activation.context, event, this)
+}
+
+/// Fires the `init` event once the root clip's class has finished
+/// construction (but before `complete` is fired).
+pub fn fire_init_event<'gc>(
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+) -> Result<(), Error> {
+    dispatch_simple_event(context, this, "init")
+}
+
+/// Fires the `complete` event once the associated stream has finished
+/// loading.
+pub fn fire_complete_event<'gc>(
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+) -> Result<(), Error> {
+    dispatch_simple_event(context, this, "complete")
+}
+
+/// Fires an `HTTPStatusEvent` carrying the status code of the load's HTTP
+/// transaction, if a transport outcome is known.
+pub fn fire_http_status_event<'gc>(
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    status: u16,
+) -> Result<(), Error> {
+    let http_status_evt_cls = context.avm2.classes().httpstatusevent;
+    let mut activation = Activation::from_nothing(context.reborrow());
+    let event = http_status_evt_cls.construct(
+        &mut activation,
+        &[
+            "httpStatus".into(),
+            false.into(),
+            false.into(),
+            status.into(),
+        ],
+    )?;
+
+    Avm2::dispatch_event(activation.context, event, this)
+}
+
+/// Fires an `IOErrorEvent` describing a failed load.
+pub fn fire_io_error_event<'gc>(
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    text: AvmString<'gc>,
+) -> Result<(), Error> {
+    let io_error_evt_cls = context.avm2.classes().ioerrorevent;
+    let mut activation = Activation::from_nothing(context.reborrow());
+    let event = io_error_evt_cls.construct(
+        &mut activation,
+        &["ioError".into(), false.into(), false.into(), text.into()],
+    )?;
+
+    Avm2::dispatch_event(activation.context, event, this)
+}
+
 /// Implements `flash.display.LoaderInfo`'s instance constructor.
 pub fn instance_init<'gc>(
     _activation: &mut Activation<'_, 'gc, '_>,
@@ -46,6 +151,11 @@ pub fn action_script_version<'gc>(
                 LoaderStream::Stage => {
                     return Err("Error: The stage's loader info does not have an AS version".into())
                 }
+                LoaderStream::Bitmap(..) => {
+                    return Err(
+                        "Error: Loaded image content does not have an AS version".into(),
+                    )
+                }
                 LoaderStream::Swf(movie, _) => {
                     let library = activation
                         .context
@@ -89,6 +199,14 @@ pub fn application_domain<'gc>(
                     )
                     .into());
                 }
+                LoaderStream::Bitmap(..) => {
+                    return Ok(DomainObject::from_domain(
+                        activation.context.gc_context,
+                        Some(activation.context.avm2.prototypes().application_domain),
+                        activation.context.avm2.global_domain(),
+                    )
+                    .into());
+                }
             }
         }
     }
@@ -97,9 +215,6 @@ pub fn application_domain<'gc>(
 }
 
 /// `bytesTotal` getter
-///
-/// TODO: This is also the getter for `bytesLoaded` as we don't yet support
-/// streaming loads yet. When we do, we'll need another property for this.
 pub fn bytes_total<'gc>(
     activation: &mut Activation<'_, 'gc, '_>,
     this: Option<Object<'gc>>,
@@ -114,13 +229,62 @@ pub fn bytes_total<'gc>(
                 LoaderStream::Swf(movie, _) => {
                     return Ok(movie.compressed_length().into());
                 }
+                LoaderStream::Bitmap(_, _, _, _, length) => return Ok((*length).into()),
+            }
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// `bytesLoaded` getter
+///
+/// Unlike `bytesTotal`, this tracks how much of the movie has actually been
+/// streamed in so far; it is advanced by `set_bytes_loaded` as the loader
+/// receives chunks of the movie. The stage's root movie is never streamed
+/// in chunks, so it's always fully loaded.
+pub fn bytes_loaded<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        if let Some(loader_stream) = this.as_loader_stream() {
+            if let LoaderStream::Stage = &*loader_stream {
+                return Ok(activation.context.swf.compressed_length().into());
             }
         }
+
+        if let Some(loader_info) = this.as_loader_info_object() {
+            return Ok(loader_info.read().loaded_bytes().into());
+        }
     }
 
     Ok(Value::Undefined)
 }
 
+/// Called by the loader as new chunks of the movie arrive.
+///
+/// Updates the `bytesLoaded` backing this `LoaderInfo` and dispatches the
+/// corresponding `ProgressEvent`. `bytes_total` is passed in by the caller
+/// rather than derived from the `LoaderStream`, since this is called while
+/// a load is still in progress, before `set_loader_stream` has anything to
+/// report on.
+pub fn set_bytes_loaded<'gc>(
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    bytes_loaded: u32,
+    bytes_total: u32,
+) -> Result<(), Error> {
+    if let Some(loader_info) = this.as_loader_info_object() {
+        loader_info
+            .write(context.gc_context)
+            .set_loaded_bytes(bytes_loaded);
+    }
+
+    fire_progress_event(context, this, bytes_loaded, bytes_total)
+}
+
 /// `content` getter
 pub fn content<'gc>(
     activation: &mut Activation<'_, 'gc, '_>,
@@ -134,6 +298,9 @@ pub fn content<'gc>(
                 LoaderStream::Swf(_, root) => {
                     return Ok(root.object2());
                 }
+                LoaderStream::Bitmap(bitmap, _, _, _, _) => {
+                    return Ok(bitmap.object2());
+                }
             }
         }
     }
@@ -154,6 +321,9 @@ pub fn content_type<'gc>(
                 LoaderStream::Swf(_, _) => {
                     return Ok("application/x-shockwave-flash".into());
                 }
+                LoaderStream::Bitmap(_, format, _, _, _) => {
+                    return Ok(format.mime_type().into());
+                }
             }
         }
     }
@@ -176,6 +346,11 @@ pub fn frame_rate<'gc>(
                 LoaderStream::Swf(root, _) => {
                     return Ok(root.header().frame_rate.into());
                 }
+                LoaderStream::Bitmap(..) => {
+                    return Err(
+                        "Error: Loaded image content does not have a frame rate".into(),
+                    )
+                }
             }
         }
     }
@@ -200,6 +375,7 @@ pub fn height<'gc>(
                     let y_max = root.header().stage_size.y_max;
                     return Ok((y_max - y_min).to_pixels().into());
                 }
+                LoaderStream::Bitmap(_, _, _, height, _) => return Ok((*height).into()),
             }
         }
     }
@@ -207,15 +383,135 @@ pub fn height<'gc>(
     Ok(Value::Undefined)
 }
 
-/// `isURLInaccessible` getter stub
+/// Returns the `scheme://host:port` origin of a URL, or `None` if it could
+/// not be parsed as an absolute URL (e.g. a bare local path).
+fn url_origin(url: &str) -> Option<(String, Option<url::Host<String>>, Option<u16>)> {
+    let parsed = url::Url::parse(url).ok()?;
+
+    Some((
+        parsed.scheme().to_string(),
+        parsed.host().map(|host| host.to_owned()),
+        parsed.port_or_known_default(),
+    ))
+}
+
+/// Two URLs are in the same security sandbox if their origins are equal, or,
+/// when neither parses as an absolute URL (e.g. two bare local paths, as with
+/// ordinary locally-run/self-loaded content), if the URLs are equal
+/// byte-for-byte. Only one side failing to parse means they're different.
+fn same_origin(a: &str, b: &str) -> bool {
+    match (url_origin(a), url_origin(b)) {
+        (Some(a), Some(b)) => a == b,
+        (None, None) => a == b,
+        _ => false,
+    }
+}
+
+/// `isURLInaccessible` getter
+///
+/// Mirrors Flash's security sandbox model: a loaded SWF is inaccessible to
+/// its loader when the two don't share an origin and no crossdomain policy
+/// file has granted cross-domain access.
+///
+/// Known gap: nothing fetches/parses a `crossdomain.xml` yet (see
+/// `LoaderInfoData::cross_domain_policy_grant`), so `granted` below is
+/// always `false` - every cross-origin load is reported inaccessible, even
+/// ones a real policy file would allow.
 pub fn is_url_inaccessible<'gc>(
     _activation: &mut Activation<'_, 'gc, '_>,
-    _this: Option<Object<'gc>>,
+    this: Option<Object<'gc>>,
     _args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        if let Some(loader_stream) = this.as_loader_stream() {
+            if let LoaderStream::Swf(root, _) = &*loader_stream {
+                let loaded_url = root.url();
+                let loader_url = root.loader_url().or_else(|| root.url());
+
+                let same_origin = match (loaded_url, loader_url) {
+                    (Some(loaded_url), Some(loader_url)) => same_origin(loaded_url, loader_url),
+                    _ => false,
+                };
+
+                if !same_origin {
+                    let granted = this
+                        .as_loader_info_object()
+                        .map(|loader_info| loader_info.read().has_cross_domain_policy_grant())
+                        .unwrap_or(false);
+
+                    return Ok((!granted).into());
+                }
+            }
+        }
+    }
+
     Ok(false.into())
 }
 
+/// `childSandboxBridge` getter
+pub fn child_sandbox_bridge<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        if let Some(loader_info) = this.as_loader_info_object() {
+            return Ok(loader_info.read().child_sandbox_bridge());
+        }
+    }
+
+    Ok(Value::Null)
+}
+
+/// `childSandboxBridge` setter
+pub fn set_child_sandbox_bridge<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        if let Some(loader_info) = this.as_loader_info_object() {
+            loader_info
+                .write(activation.context.gc_context)
+                .set_child_sandbox_bridge(args.get(0).cloned().unwrap_or(Value::Undefined));
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// `parentSandboxBridge` getter
+pub fn parent_sandbox_bridge<'gc>(
+    _activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        if let Some(loader_info) = this.as_loader_info_object() {
+            return Ok(loader_info.read().parent_sandbox_bridge());
+        }
+    }
+
+    Ok(Value::Null)
+}
+
+/// `parentSandboxBridge` setter
+pub fn set_parent_sandbox_bridge<'gc>(
+    activation: &mut Activation<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error> {
+    if let Some(this) = this {
+        if let Some(loader_info) = this.as_loader_info_object() {
+            loader_info
+                .write(activation.context.gc_context)
+                .set_parent_sandbox_bridge(args.get(0).cloned().unwrap_or(Value::Undefined));
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
 /// `swfVersion` getter
 pub fn swf_version<'gc>(
     _activation: &mut Activation<'_, 'gc, '_>,
@@ -231,6 +527,11 @@ pub fn swf_version<'gc>(
                 LoaderStream::Swf(root, _) => {
                     return Ok(root.header().version.into());
                 }
+                LoaderStream::Bitmap(..) => {
+                    return Err(
+                        "Error: Loaded image content does not have a SWF version".into(),
+                    )
+                }
             }
         }
     }
@@ -254,6 +555,9 @@ pub fn url<'gc>(
                     let url = root.url().unwrap_or("").to_string();
                     return Ok(AvmString::new(activation.context.gc_context, url).into());
                 }
+                LoaderStream::Bitmap(..) => {
+                    return Err("Error: Loaded image content does not yet track a URL".into())
+                }
             }
         }
     }
@@ -278,6 +582,7 @@ pub fn width<'gc>(
                     let x_max = root.header().stage_size.x_max;
                     return Ok((x_max - x_min).to_pixels().into());
                 }
+                LoaderStream::Bitmap(_, _, width, _, _) => return Ok((*width).into()),
             }
         }
     }
@@ -303,34 +608,48 @@ pub fn bytes<'gc>(
                         ByteArrayObject::construct(activation.context.gc_context, Some(ba_proto));
                     let mut ba_write = ba.as_bytearray_mut(activation.context.gc_context).unwrap();
 
-                    // First, write a fake header corresponding to an
-                    // uncompressed SWF
-                    let mut header = root.header().clone();
-                    header.compression = Compression::None;
-                    header.uncompressed_length = root.data().len() as u32;
-
-                    write_swf(
-                        &Swf {
-                            header,
-                            tags: vec![],
-                        },
-                        &mut *ba_write,
-                    )
-                    .unwrap();
-
-                    // `swf` always writes an implicit end tag, let's cut that
-                    // off. We scroll back 2 bytes before writing the actual
-                    // datastream as it is guaranteed to at least be as long as
-                    // the implicit end tag we want to get rid of.
-                    let correct_header_length = ba_write.bytes().len() - 2;
-                    ba_write.set_position(correct_header_length);
-                    ba_write.write_bytes(root.data());
-
-                    // `swf` wrote the wrong length (since we wrote the data
-                    // ourselves), so we need to overwrite it ourselves.
-                    ba_write.set_position(4);
-                    ba_write.set_endian(Endian::Little);
-                    ba_write.write_unsigned_int((root.data().len() + correct_header_length) as u32);
+                    if let Some(compressed_data) = root.compressed_data() {
+                        // The original container (signature, header and
+                        // compressed payload) is still around, so hand it
+                        // back exactly as it was delivered - Flash never
+                        // re-compresses or decompresses `loaderInfo.bytes`.
+                        ba_write.write_bytes(compressed_data);
+                    } else {
+                        // We don't know what the original compression was
+                        // (or there wasn't one), so fall back to
+                        // re-emitting an uncompressed container with a
+                        // corrected `uncompressed_length`.
+                        let mut header = root.header().clone();
+                        header.compression = Compression::None;
+                        header.uncompressed_length = root.data().len() as u32;
+
+                        write_swf(
+                            &Swf {
+                                header,
+                                tags: vec![],
+                            },
+                            &mut *ba_write,
+                        )
+                        .unwrap();
+
+                        // `swf` always writes an implicit end tag, let's cut
+                        // that off. We scroll back 2 bytes before writing the
+                        // actual datastream as it is guaranteed to at least
+                        // be as long as the implicit end tag we want to get
+                        // rid of.
+                        let correct_header_length = ba_write.bytes().len() - 2;
+                        ba_write.set_position(correct_header_length);
+                        ba_write.write_bytes(root.data());
+
+                        // `swf` wrote the wrong length (since we wrote the
+                        // data ourselves), so we need to overwrite it
+                        // ourselves.
+                        ba_write.set_position(4);
+                        ba_write.set_endian(Endian::Little);
+                        ba_write.write_unsigned_int(
+                            (root.data().len() + correct_header_length) as u32,
+                        );
+                    }
 
                     // Finally, reset the array to the correct state.
                     ba_write.set_position(0);
@@ -338,6 +657,9 @@ pub fn bytes<'gc>(
 
                     return Ok(ba.into());
                 }
+                LoaderStream::Bitmap(..) => {
+                    return Err("Error: Loaded image content does not yet expose a bytestream".into())
+                }
             }
         }
     }
@@ -365,6 +687,9 @@ pub fn loader_url<'gc>(
                         .to_string();
                     return Ok(AvmString::new(activation.context.gc_context, loader_url).into());
                 }
+                LoaderStream::Bitmap(..) => {
+                    return Err("Error: Loaded image content does not yet track a loader URL".into())
+                }
             }
         }
     }
@@ -401,6 +726,12 @@ pub fn parameters<'gc>(
                         )?;
                     }
 
+                    return Ok(params_obj.into());
+                }
+                LoaderStream::Bitmap(..) => {
+                    let object_proto = activation.context.avm2.prototypes().object;
+                    let params_obj = ScriptObject::object(activation.context.gc_context, object_proto);
+
                     return Ok(params_obj.into());
                 }
             }
@@ -444,7 +775,7 @@ pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>
     ));
     write.define_instance_trait(Trait::from_getter(
         QName::new(Namespace::public(), "bytesLoaded"),
-        Method::from_builtin(bytes_total),
+        Method::from_builtin(bytes_loaded),
     ));
     write.define_instance_trait(Trait::from_getter(
         QName::new(Namespace::public(), "bytesTotal"),
@@ -470,6 +801,22 @@ pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>
         QName::new(Namespace::public(), "isURLInaccessible"),
         Method::from_builtin(is_url_inaccessible),
     ));
+    write.define_instance_trait(Trait::from_getter(
+        QName::new(Namespace::public(), "childSandboxBridge"),
+        Method::from_builtin(child_sandbox_bridge),
+    ));
+    write.define_instance_trait(Trait::from_setter(
+        QName::new(Namespace::public(), "childSandboxBridge"),
+        Method::from_builtin(set_child_sandbox_bridge),
+    ));
+    write.define_instance_trait(Trait::from_getter(
+        QName::new(Namespace::public(), "parentSandboxBridge"),
+        Method::from_builtin(parent_sandbox_bridge),
+    ));
+    write.define_instance_trait(Trait::from_setter(
+        QName::new(Namespace::public(), "parentSandboxBridge"),
+        Method::from_builtin(set_parent_sandbox_bridge),
+    ));
     write.define_instance_trait(Trait::from_getter(
         QName::new(Namespace::public(), "swfVersion"),
         Method::from_builtin(swf_version),
@@ -497,3 +844,37 @@ pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>
 
     class
 }
+
+#[cfg(test)]
+mod tests {
+    use super::same_origin;
+
+    #[test]
+    fn differing_origins_are_not_same_origin() {
+        assert!(!same_origin(
+            "https://example.com/a.swf",
+            "https://evil.example.org/b.swf"
+        ));
+    }
+
+    #[test]
+    fn matching_origins_are_same_origin_regardless_of_path() {
+        assert!(same_origin(
+            "https://example.com/a.swf",
+            "https://example.com/other/b.swf"
+        ));
+    }
+
+    #[test]
+    fn matching_unparseable_local_paths_are_same_origin() {
+        // Ordinary local/self-loaded content has no scheme at all, so both
+        // sides fail to parse as an absolute URL - that must not be treated
+        // as "different origins".
+        assert!(same_origin("movie.swf", "movie.swf"));
+    }
+
+    #[test]
+    fn differing_unparseable_local_paths_are_not_same_origin() {
+        assert!(!same_origin("a.swf", "b.swf"));
+    }
+}
@@ -0,0 +1,218 @@
+//! Data backing `flash.display.LoaderInfo` objects.
+//!
+//! Declared from `avm2::object` as `pub mod loaderinfo_object;`.
+
+use crate::avm2::value::Value;
+use crate::display_object::DisplayObject;
+use crate::tag_utils::SwfMovie;
+use gc_arena::{Collect, GcCell};
+use std::sync::Arc;
+
+/// The underlying content a `LoaderInfo` is reporting on.
+#[derive(Clone, Collect)]
+#[collect(no_drop)]
+pub enum LoaderStream<'gc> {
+    /// The browser-level movie stage, which wasn't loaded by any `Loader`.
+    Stage,
+
+    /// A loaded SWF movie, and the display object constructed from it.
+    Swf(Arc<SwfMovie>, DisplayObject<'gc>),
+
+    /// A loaded non-SWF image, and the `Bitmap` constructed from it, its
+    /// format, pixel width, pixel height, and the length in bytes of the
+    /// source file.
+    Bitmap(DisplayObject<'gc>, ImageFileFormat, u32, u32, u32),
+}
+
+/// The format of a non-SWF image loaded via `Loader.load`.
+///
+/// `LoaderStream::Bitmap` carries one of these, alongside the decoded
+/// `Bitmap`, its pixel dimensions, and the length of the source file in
+/// bytes, so that `contentType`/`width`/`height`/`bytesTotal` can report
+/// the values Flash Player would have detected from the file itself.
+#[derive(Clone, Copy, Collect, Debug, Eq, PartialEq)]
+#[collect(require_static)]
+pub enum ImageFileFormat {
+    Jpeg,
+    Png,
+    Gif,
+}
+
+impl ImageFileFormat {
+    /// Sniffs the format of an image from its leading magic bytes, as
+    /// returned by a `Loader.load`/`Loader.loadBytes` network response.
+    pub fn sniff(data: &[u8]) -> Option<Self> {
+        if data.starts_with(&[0xFF, 0xD8, 0xFF]) {
+            Some(ImageFileFormat::Jpeg)
+        } else if data.starts_with(&[0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A]) {
+            Some(ImageFileFormat::Png)
+        } else if data.starts_with(b"GIF87a") || data.starts_with(b"GIF89a") {
+            Some(ImageFileFormat::Gif)
+        } else {
+            None
+        }
+    }
+
+    pub fn mime_type(self) -> &'static str {
+        match self {
+            ImageFileFormat::Jpeg => "image/jpeg",
+            ImageFileFormat::Png => "image/png",
+            ImageFileFormat::Gif => "image/gif",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ImageFileFormat;
+
+    #[test]
+    fn sniffs_known_formats() {
+        assert_eq!(
+            ImageFileFormat::sniff(&[0xFF, 0xD8, 0xFF, 0xE0]),
+            Some(ImageFileFormat::Jpeg)
+        );
+        assert_eq!(
+            ImageFileFormat::sniff(&[0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A]),
+            Some(ImageFileFormat::Png)
+        );
+        assert_eq!(ImageFileFormat::sniff(b"GIF89a..."), Some(ImageFileFormat::Gif));
+        assert_eq!(ImageFileFormat::sniff(b"not an image"), None);
+    }
+
+    #[test]
+    fn mime_types_match_format() {
+        assert_eq!(ImageFileFormat::Jpeg.mime_type(), "image/jpeg");
+        assert_eq!(ImageFileFormat::Png.mime_type(), "image/png");
+        assert_eq!(ImageFileFormat::Gif.mime_type(), "image/gif");
+    }
+}
+
+/// The mutable state backing a `LoaderInfo` object - everything that isn't
+/// derivable from the `LoaderStream` itself once a load has completed.
+#[derive(Collect)]
+#[collect(no_drop)]
+pub struct LoaderInfoData<'gc> {
+    loader_stream: Option<LoaderStream<'gc>>,
+
+    /// How many bytes of the movie have actually streamed in so far.
+    loaded_bytes: u32,
+
+    /// Whether a crossdomain policy file has granted the loader access to
+    /// this (otherwise cross-origin) content.
+    ///
+    /// Nothing fetches or parses a `crossdomain.xml` yet, so this is always
+    /// `false` and `grant_cross_domain_policy` has no caller - same kind of
+    /// gap as the network transport `load_swf_into` still needs, and left
+    /// for the same reason: it's the `LoadManager`/policy-file fetch's job,
+    /// not this module's. Until that lands, `isURLInaccessible` fails closed
+    /// for every cross-origin load, including ones a real policy would
+    /// allow.
+    cross_domain_policy_grant: bool,
+
+    /// The `childSandboxBridge`/`parentSandboxBridge` values used by loaded
+    /// and loading content to talk to each other across a sandbox boundary.
+    child_sandbox_bridge: Value<'gc>,
+    parent_sandbox_bridge: Value<'gc>,
+}
+
+impl<'gc> Default for LoaderInfoData<'gc> {
+    fn default() -> Self {
+        Self {
+            loader_stream: None,
+            loaded_bytes: 0,
+            cross_domain_policy_grant: false,
+            child_sandbox_bridge: Value::Undefined,
+            parent_sandbox_bridge: Value::Undefined,
+        }
+    }
+}
+
+impl<'gc> LoaderInfoData<'gc> {
+    pub fn loader_stream(&self) -> Option<&LoaderStream<'gc>> {
+        self.loader_stream.as_ref()
+    }
+
+    pub fn set_loader_stream(&mut self, stream: LoaderStream<'gc>) {
+        self.loader_stream = Some(stream);
+    }
+
+    pub fn loaded_bytes(&self) -> u32 {
+        self.loaded_bytes
+    }
+
+    pub fn set_loaded_bytes(&mut self, loaded_bytes: u32) {
+        self.loaded_bytes = loaded_bytes;
+    }
+
+    pub fn has_cross_domain_policy_grant(&self) -> bool {
+        self.cross_domain_policy_grant
+    }
+
+    pub fn grant_cross_domain_policy(&mut self) {
+        self.cross_domain_policy_grant = true;
+    }
+
+    pub fn child_sandbox_bridge(&self) -> Value<'gc> {
+        self.child_sandbox_bridge
+    }
+
+    pub fn set_child_sandbox_bridge(&mut self, bridge: Value<'gc>) {
+        self.child_sandbox_bridge = bridge;
+    }
+
+    pub fn parent_sandbox_bridge(&self) -> Value<'gc> {
+        self.parent_sandbox_bridge
+    }
+
+    pub fn set_parent_sandbox_bridge(&mut self, bridge: Value<'gc>) {
+        self.parent_sandbox_bridge = bridge;
+    }
+}
+
+/// A `LoaderInfo`'s GC-managed backing data.
+pub type LoaderInfoObject<'gc> = GcCell<'gc, LoaderInfoData<'gc>>;
+
+#[cfg(test)]
+mod loader_info_data_tests {
+    use super::LoaderInfoData;
+
+    /// `loader::load_swf_into` advances `loaded_bytes` in chunks before
+    /// `set_loader_stream` is ever called (the movie isn't parsed until
+    /// every chunk has "arrived"), so `loaded_bytes`/`set_loaded_bytes` must
+    /// not depend on a `loader_stream` already being set. Exercises the same
+    /// chunked-progress loop `load_swf_into` runs, directly against the
+    /// state `set_bytes_loaded` mutates.
+    #[test]
+    fn loaded_bytes_advances_with_no_loader_stream_set() {
+        let mut data = LoaderInfoData::default();
+        assert!(data.loader_stream().is_none());
+        assert_eq!(data.loaded_bytes(), 0);
+
+        const CHUNK_SIZE: u32 = 4096;
+        let bytes_total: u32 = 10_000;
+        let mut bytes_loaded = 0;
+        while bytes_loaded < bytes_total {
+            bytes_loaded = (bytes_loaded + CHUNK_SIZE).min(bytes_total);
+            data.set_loaded_bytes(bytes_loaded);
+            assert_eq!(data.loaded_bytes(), bytes_loaded);
+        }
+
+        assert_eq!(data.loaded_bytes(), bytes_total);
+    }
+
+    /// `loader::load_image_into` has no chunked tag-parsing loop to advance
+    /// through - it reports the full size in one `set_bytes_loaded` call, the
+    /// same as `load_swf_into`'s final chunk, before any `LoaderStream::Bitmap`
+    /// is set. Checks that a single `set_loaded_bytes` of the full size, with
+    /// no loader stream set, leaves `loaded_bytes` at that size.
+    #[test]
+    fn loaded_bytes_reaches_total_for_a_single_shot_image_load() {
+        let mut data = LoaderInfoData::default();
+        let bytes_total: u32 = 2_048;
+
+        data.set_loaded_bytes(bytes_total);
+
+        assert_eq!(data.loaded_bytes(), bytes_total);
+    }
+}
@@ -0,0 +1,82 @@
+//! Core data about a loaded SWF, independent of any playback state.
+
+use std::collections::HashMap;
+
+/// A loaded SWF. This can be the root movie, or a sub-movie loaded into a
+/// `Loader` via `Loader.load`/`Loader.loadBytes`.
+#[derive(Debug)]
+pub struct SwfMovie {
+    header: swf::Header,
+
+    /// The decompressed, tag-encoded body of the SWF, past the header.
+    data: Vec<u8>,
+
+    url: Option<String>,
+    loader_url: Option<String>,
+    parameters: HashMap<String, String>,
+
+    /// The length, in bytes, of the file this movie was loaded from.
+    compressed_len: usize,
+
+    /// The original container `swf_data` was loaded from, signature, header,
+    /// and all, exactly as delivered - so that `LoaderInfo.bytes` can hand it
+    /// back without Flash's `bytes` getter ever re-compressing or
+    /// decompressing a movie that's still in flight.
+    ///
+    /// `from_data` always sets this. Where it is `None`, `bytes` falls back
+    /// to re-emitting an uncompressed container.
+    compressed_data: Option<Vec<u8>>,
+}
+
+impl SwfMovie {
+    /// Parses `swf_data` (a complete SWF file, in whatever compression the
+    /// source served it in) into a `SwfMovie` loaded from `url` by
+    /// `loader_url`.
+    pub fn from_data(
+        swf_data: &[u8],
+        url: Option<String>,
+        loader_url: Option<String>,
+    ) -> Result<Self, Box<dyn std::error::Error>> {
+        let swf_buf = swf::decompress_swf(swf_data)?;
+
+        Ok(Self {
+            header: swf_buf.header,
+            data: swf_buf.data,
+            url,
+            loader_url,
+            parameters: HashMap::new(),
+            compressed_len: swf_data.len(),
+            compressed_data: Some(swf_data.to_vec()),
+        })
+    }
+
+    pub fn header(&self) -> &swf::Header {
+        &self.header
+    }
+
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    pub fn url(&self) -> Option<&str> {
+        self.url.as_deref()
+    }
+
+    pub fn loader_url(&self) -> Option<&str> {
+        self.loader_url.as_deref()
+    }
+
+    pub fn parameters(&self) -> &HashMap<String, String> {
+        &self.parameters
+    }
+
+    pub fn compressed_length(&self) -> usize {
+        self.compressed_len
+    }
+
+    /// The original container this movie was loaded from, exactly as
+    /// delivered, or `None` if this movie wasn't constructed from raw bytes.
+    pub fn compressed_data(&self) -> Option<&[u8]> {
+        self.compressed_data.as_deref()
+    }
+}